@@ -1,240 +1,1016 @@
-use std::process::{Child, Command, Stdio};
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex as SyncMutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{oneshot, Mutex as AsyncMutex};
 
-/// Backend process handle
+use crate::config::ConfigManager;
+
+/// Tauri event emitted for every line the backend writes to stdout/stderr.
+const BACKEND_LOG_EVENT: &str = "backend://log";
+
+/// Tauri event emitted whenever the supervisor's view of backend health changes.
+const BACKEND_STATE_EVENT: &str = "backend://state";
+
+/// One line of backend output, forwarded to the frontend as it's produced.
+#[derive(Clone, Serialize)]
+struct BackendLogLine {
+    stream: &'static str,
+    timestamp: String,
+    text: String,
+}
+
+/// Health states reported by the supervisor loop, in the order a backend
+/// typically moves through them: `Healthy` <-> `Unhealthy` -> `Restarting`
+/// -> `Healthy` again, or `Failed` once the restart policy is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendState {
+    Healthy,
+    Unhealthy,
+    Restarting,
+    Failed,
+}
+
+/// How the supervisor decides whether a flaky backend is worth restarting.
+///
+/// Restarts are counted within a sliding `window`; once `max_restarts` have
+/// happened in that window the supervisor gives up and reports `Failed`
+/// instead of restart-looping forever.
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    pub max_restarts: u32,
+    pub window: Duration,
+    pub backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            window: Duration::from_secs(60),
+            backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Number of consecutive failed health checks before the supervisor treats
+/// the backend as down and starts counting toward a restart.
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// How often the supervisor polls backend health once it's up and running.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default time `stop()` waits for the backend to exit after a graceful
+/// termination signal before escalating to a hard kill. Overridable via
+/// `[app] shutdown_grace_period_secs`.
+const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How many attempts in all `BackendProcess::start` makes, each with a
+/// freshly allocated ephemeral port, when `dynamic_port` is set and the child
+/// fails to come up (the narrow race where the port we handed it gets taken
+/// before it binds).
+const PORT_ALLOCATION_RETRIES: u32 = 3;
+
+/// Trailing lines from the session log tailed into a readiness failure
+/// message, so a crashed launch is diagnosable without hunting for the log
+/// file by hand.
+const LOG_TAIL_LINES: usize = 20;
+
+/// One backend (or auxiliary) service as declared in the services file,
+/// e.g.:
+///
+/// ```yaml
+/// services:
+///   - name: backend
+///     command: /path/to/venv/bin/uvicorn
+///     args: ["app.main:app", "--host", "127.0.0.1", "--port", "8000"]
+///     port: 8000
+///     env:
+///       DATABASE_PATH: /path/to/expense.db
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default = "default_health_path")]
+    pub health_path: String,
+    #[serde(default = "default_startup_timeout_secs")]
+    pub startup_timeout_secs: u64,
+    pub port: u16,
+    /// Request an OS-assigned ephemeral port instead of binding `port`
+    /// directly, retrying with a fresh one if the child can't bind it.
+    #[serde(default)]
+    pub dynamic_port: bool,
+}
+
+fn default_health_path() -> String {
+    "/api/health".to_string()
+}
+
+fn default_startup_timeout_secs() -> u64 {
+    5
+}
+
+/// Top-level shape of the services file. YAML is assumed unless the path
+/// ends in `.toml`.
+#[derive(Debug, Clone, Deserialize, Default)]
+struct ServicesFile {
+    #[serde(default)]
+    services: Vec<ServiceConfig>,
+}
+
+/// Load the declarative services list from `path`, if it exists and
+/// declares at least one service. Returns `None` when there's no file (or
+/// an empty one), so the caller can fall back to the single-`"backend"`
+/// default that matches today's hardcoded dev/prod launch logic.
+fn load_services_file(path: &Path) -> Result<Option<Vec<ServiceConfig>>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read services file {:?}: {}", path, e))?;
+
+    let parsed: ServicesFile = if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+        toml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse services file {:?}: {}", path, e))?
+    } else {
+        serde_yaml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse services file {:?}: {}", path, e))?
+    };
+
+    if parsed.services.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(parsed.services))
+    }
+}
+
+/// Build the single `"backend"` service matching today's hardcoded dev/prod
+/// launch logic (binary resolution via `resolve_backend_executable`, plus
+/// `--host`/`--port` CLI args in debug builds), used when no services file
+/// is present.
+///
+/// Opts into ephemeral-port allocation by default (`[app] dynamic_port`,
+/// default `true`) so the common "configured port is already occupied"
+/// failure is solved out of the box, not just for services.yaml users.
+fn build_default_service(database_path: &str, port: u16, config: &ConfigManager) -> Result<ServiceConfig, String> {
+    let (command, mut args) = resolve_backend_executable(config)?;
+
+    #[cfg(debug_assertions)]
+    {
+        args.push("--host".to_string());
+        args.push("127.0.0.1".to_string());
+        args.push("--port".to_string());
+        args.push(port.to_string());
+    }
+
+    let mut env = HashMap::new();
+    env.insert("DATABASE_PATH".to_string(), database_path.to_string());
+    env.insert("PORT".to_string(), port.to_string());
+
+    Ok(ServiceConfig {
+        name: "backend".to_string(),
+        command,
+        args,
+        working_dir: None,
+        env,
+        health_path: default_health_path(),
+        startup_timeout_secs: default_startup_timeout_secs(),
+        port,
+        dynamic_port: config.get_bool("app", "dynamic_port", true),
+    })
+}
+
+/// Bind a `TcpListener` to an OS-assigned ephemeral port, read the port
+/// back, and drop the listener so the child can bind it instead. There's a
+/// narrow window between the drop here and the child's own bind where
+/// something else could grab the port, which is why callers retry.
+fn allocate_ephemeral_port() -> Result<u16, String> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| format!("Failed to allocate an ephemeral port: {}", e))?;
+    listener
+        .local_addr()
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to read allocated port: {}", e))
+}
+
+/// Point `service` at `port`: updates the `PORT` env var and, if present,
+/// the value following a `--port` CLI arg (added by `build_default_service`
+/// for debug builds), so a port resolved after the service was built still
+/// reaches the child consistently.
+fn apply_port(service: &mut ServiceConfig, port: u16) {
+    service.env.insert("PORT".to_string(), port.to_string());
+    if let Some(pos) = service.args.iter().position(|a| a == "--port") {
+        if let Some(value) = service.args.get_mut(pos + 1) {
+            *value = port.to_string();
+        }
+    }
+    service.port = port;
+}
+
+/// Backend process handle.
+///
+/// Owns the child indirectly through `process`, which is shared with a
+/// background supervisor task so the supervisor can tear down and replace a
+/// dead child without the two ever racing on who owns it. `port` is shared
+/// the same way, since a `dynamic_port` service gets a fresh port from the
+/// supervisor on every restart.
 pub struct BackendProcess {
-    process: Option<Child>,
-    port: u16,
+    process: Arc<AsyncMutex<Option<Child>>>,
+    port: Arc<SyncMutex<u16>>,
+    supervisor_cancel: Option<oneshot::Sender<()>>,
+    shutdown_grace_period: Duration,
 }
 
 impl BackendProcess {
-    /// Start the backend server with the specified database path
-    pub async fn start(database_path: &str, port: u16) -> Result<Self, String> {
-        // Get the backend binary path
-        let backend_binary = get_backend_binary_path()?;
-        
-        eprintln!("🚀 [BACKEND] Starting backend process...");
-        eprintln!("   Binary: {} {:?}", backend_binary.0, backend_binary.1);
-        eprintln!("   Database: {}", database_path);
-        eprintln!("   Port: {}", port);
-
-        // Create log files for this session
-        let log_file = create_backend_log_file()?;
-        eprintln!("   Log file: {:?}", log_file);
-
-        // Start the backend process
-        let mut cmd = Command::new(&backend_binary.0);
-        cmd.args(&backend_binary.1);
-        
-        // In development mode with uvicorn, pass host and port as CLI args
-        #[cfg(debug_assertions)]
-        {
-            cmd.arg("--host").arg("127.0.0.1");
-            cmd.arg("--port").arg(port.to_string());
+    /// Start a single service as declared by `service`.
+    ///
+    /// When `service.dynamic_port` is set, `service.port` is treated as a
+    /// hint rather than a hard requirement: each attempt binds a fresh
+    /// OS-assigned ephemeral port and hands that to the child instead,
+    /// making up to `PORT_ALLOCATION_RETRIES` attempts in all if the child
+    /// doesn't come up (e.g. because something else grabbed the port between us
+    /// releasing it and the child binding it).
+    async fn start(
+        service: &ServiceConfig,
+        config: &ConfigManager,
+        app_handle: AppHandle,
+    ) -> Result<Self, String> {
+        let attempts = if service.dynamic_port { PORT_ALLOCATION_RETRIES } else { 1 };
+        let mut last_err = String::new();
+
+        for attempt in 1..=attempts {
+            let mut service = service.clone();
+            if service.dynamic_port {
+                let port = allocate_ephemeral_port()?;
+                log::info!(
+                    "Requesting ephemeral port {} for '{}' (attempt {}/{})",
+                    port, service.name, attempt, attempts
+                );
+                apply_port(&mut service, port);
+            }
+
+            let (child, log_file) = spawn_child(&service, app_handle.clone()).await?;
+            let process = Arc::new(AsyncMutex::new(Some(child)));
+
+            log::info!("Waiting for '{}' to be ready", service.name);
+            let timeout = Duration::from_secs(service.startup_timeout_secs);
+            if let Err(e) = wait_for_ready(&process, service.port, &service.health_path, timeout, &log_file).await {
+                log::warn!(
+                    "Service '{}' failed to start on port {} (attempt {}/{}): {}",
+                    service.name, service.port, attempt, attempts, e
+                );
+                kill_locked_child(&process).await;
+                last_err = format!("Service '{}' failed to start: {}", service.name, e);
+                continue;
+            }
+
+            log::info!("Service '{}' is ready on port {}", service.name, service.port);
+
+            let shutdown_grace_period = Duration::from_secs(
+                config.get_int("app", "shutdown_grace_period_secs", DEFAULT_SHUTDOWN_GRACE_PERIOD.as_secs() as i64) as u64,
+            );
+
+            let port = Arc::new(SyncMutex::new(service.port));
+
+            let supervisor_cancel = spawn_supervisor(
+                process.clone(),
+                service.clone(),
+                app_handle,
+                RestartPolicy::default(),
+                port.clone(),
+            );
+
+            return Ok(BackendProcess {
+                process,
+                port,
+                supervisor_cancel: Some(supervisor_cancel),
+                shutdown_grace_period,
+            });
         }
-        
-        // Also set environment variables for compatibility
-        cmd.env("DATABASE_PATH", database_path);
-        cmd.env("PORT", port.to_string());
-        
-        // Redirect stdout and stderr to log file
-        let log_file_stdout = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_file)
-            .map_err(|e| format!("Failed to open log file for stdout: {}", e))?;
-        
-        let log_file_stderr = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_file)
-            .map_err(|e| format!("Failed to open log file for stderr: {}", e))?;
-        
-        cmd.stdout(Stdio::from(log_file_stdout));
-        cmd.stderr(Stdio::from(log_file_stderr));
-        
-        let child = cmd.spawn()
-            .map_err(|e| {
-                eprintln!("❌ [BACKEND] Failed to spawn process: {}", e);
-                format!("Failed to start backend: {}", e)
-            })?;
 
-        eprintln!("✓ [BACKEND] Process spawned with PID: {:?}", child.id());
+        log::error!("{}", last_err);
+        Err(last_err)
+    }
 
-        let mut backend = BackendProcess {
-            process: Some(child),
-            port,
-        };
+    /// The port this service actually ended up listening on. Reads the
+    /// shared state a `dynamic_port` supervisor keeps current across
+    /// restarts, so it's never stale even right after a reallocation.
+    pub fn port(&self) -> u16 {
+        *self.port.lock().unwrap()
+    }
 
-        // Wait for backend to be ready
-        eprintln!("⏳ [BACKEND] Waiting for backend to be ready...");
-        if !backend.wait_for_ready(Duration::from_secs(5)).await? {
-            eprintln!("❌ [BACKEND] Backend failed to start within timeout");
-            backend.stop();
-            return Err("Backend failed to start within timeout".to_string());
+    /// Stop the backend process and its supervisor.
+    ///
+    /// Sends a graceful termination signal first and gives the backend
+    /// `shutdown_grace_period` to exit on its own (so uvicorn can flush the
+    /// SQLite database and close connections cleanly), only escalating to a
+    /// hard kill if it's still alive afterward.
+    ///
+    /// Cancellation is only observed by the supervisor at its next `select!`
+    /// point, so a `stop()` that lands mid-restart can find the lock held.
+    /// Rather than silently skipping termination (which could let the
+    /// supervisor swap in a fresh, unsupervised child right after we give
+    /// up), this retries for up to `shutdown_grace_period` before falling
+    /// back to `kill_on_drop` as the last resort.
+    pub fn stop(&mut self) {
+        if let Some(cancel) = self.supervisor_cancel.take() {
+            let _ = cancel.send(());
         }
 
-        eprintln!("✅ [BACKEND] Backend is ready!");
-        Ok(backend)
-    }
-
-    /// Check if backend is healthy
-    async fn check_health(&self) -> bool {
-        let url = format!("http://127.0.0.1:{}/api/health", self.port);
-        
-        match reqwest::get(&url).await {
-            Ok(response) => {
-                let is_ok = response.status().is_success();
-                if is_ok {
-                    eprintln!("✓ [BACKEND] Health check passed");
-                } else {
-                    eprintln!("⚠️  [BACKEND] Health check failed with status: {}", response.status());
+        let deadline = Instant::now() + self.shutdown_grace_period;
+        loop {
+            match self.process.try_lock() {
+                Ok(mut guard) => {
+                    if let Some(mut child) = guard.take() {
+                        log::info!("Stopping backend");
+                        terminate_gracefully(&mut child, self.shutdown_grace_period);
+                    }
+                    return;
+                }
+                Err(_) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(_) => {
+                    log::warn!(
+                        "Could not acquire the backend lock within the shutdown grace period; \
+                         relying on kill_on_drop to reap it if the supervisor swaps in a new child"
+                    );
+                    return;
                 }
-                is_ok
             }
+        }
+    }
+
+    /// Check if the process is still running
+    pub fn is_running(&mut self) -> bool {
+        match self.process.try_lock() {
+            Ok(mut guard) => match guard.as_mut() {
+                Some(child) => matches!(child.try_wait(), Ok(None)),
+                None => false,
+            },
+            // Lock is held by the supervisor, most likely mid-restart.
+            Err(_) => true,
+        }
+    }
+}
+
+impl Drop for BackendProcess {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Starts, tracks, and stops the set of backend services declared in the
+/// services file (see `config::get_services_config_path`), falling back to
+/// a single `"backend"` service matching today's dev/prod launch logic when
+/// no file is present. This replaces the former compile-time
+/// `#[cfg(debug_assertions)]` switch with something power users can edit
+/// without rebuilding.
+///
+/// Stopping is implicit: dropping the manager drops each `BackendProcess`,
+/// which stops it the same way a single backend always has.
+pub struct BackendManager {
+    processes: HashMap<String, BackendProcess>,
+}
+
+impl BackendManager {
+    /// Start every declared service, stopping early (and tearing down
+    /// whatever already started) if one of them fails.
+    pub async fn start(
+        database_path: &str,
+        port: u16,
+        config: &ConfigManager,
+        app_handle: AppHandle,
+    ) -> Result<Self, String> {
+        let services = match load_services_file(&crate::config::get_services_config_path())? {
+            Some(services) => services,
+            None => vec![build_default_service(database_path, port, config)?],
+        };
+
+        let mut processes = HashMap::new();
+        for service in services {
+            log::info!("Starting service '{}'", service.name);
+            let process = BackendProcess::start(&service, config, app_handle.clone()).await?;
+            processes.insert(service.name.clone(), process);
+        }
+
+        Ok(Self { processes })
+    }
+
+    /// The port the named service is currently listening on, if it's
+    /// running. Reflects reallocation on restart for `dynamic_port` services.
+    pub fn port(&self, name: &str) -> Option<u16> {
+        self.processes.get(name).map(|p| p.port())
+    }
+}
+
+/// Take and kill whatever child is currently behind `process`, if any.
+///
+/// Used by the supervisor when tearing down a child it has already
+/// determined is dead or unresponsive, so an immediate kill (rather than
+/// `terminate_gracefully`'s SIGTERM-then-wait) is appropriate.
+async fn kill_locked_child(process: &Arc<AsyncMutex<Option<Child>>>) {
+    let mut guard = process.lock().await;
+    if let Some(mut child) = guard.take() {
+        let _ = child.start_kill();
+    }
+}
+
+/// Ask `child` to exit gracefully and only force-kill it if it doesn't.
+///
+/// On Unix this sends `SIGTERM` to the child's process group (so uvicorn's
+/// own subprocesses, e.g. reload workers, are terminated too), then polls
+/// `try_wait` for up to `grace_period` before escalating to `SIGKILL`. The
+/// child must have been spawned with `process_group(0)` for the group
+/// signal to target more than just the child itself.
+fn terminate_gracefully(child: &mut Child, grace_period: Duration) {
+    let Some(pid) = child.id() else {
+        // Already reaped; nothing left to signal.
+        return;
+    };
+
+    #[cfg(unix)]
+    {
+        // A negative pid targets the whole process group. Since the child
+        // is spawned as its own group leader, this also reaches uvicorn's
+        // reload/worker subprocesses.
+        let pgid = pid as libc::pid_t;
+        if unsafe { libc::kill(-pgid, libc::SIGTERM) } != 0 {
+            // Not a group leader (or already gone); fall back to signaling
+            // just the child.
+            unsafe {
+                libc::kill(pgid, libc::SIGTERM);
+            }
+        }
+        log::info!("Sent SIGTERM to backend (pid {})", pid);
+    }
+
+    #[cfg(windows)]
+    {
+        // Windows has no SIGTERM equivalent reachable from here without the
+        // child sharing our console (CTRL_BREAK) or a DLL injected handler;
+        // we give the grace-period loop below a chance to observe a normal
+        // exit before falling back to TerminateProcess via `start_kill`.
+        log::info!("Waiting for backend to exit (pid {})", pid);
+    }
+
+    let start = Instant::now();
+    while start.elapsed() < grace_period {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                log::info!("Backend exited gracefully: {:?}", status);
+                return;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
             Err(e) => {
-                eprintln!("⚠️  [BACKEND] Health check error: {}", e);
-                false
+                log::warn!("Error polling backend during shutdown: {}", e);
+                break;
             }
         }
     }
 
-    /// Wait for backend to be ready with timeout
-    async fn wait_for_ready(&mut self, timeout: Duration) -> Result<bool, String> {
-        let start = std::time::Instant::now();
-        let check_interval = Duration::from_millis(200);
+    log::warn!("Backend did not exit within grace period, forcing kill");
+    let _ = child.start_kill();
+}
+
+/// Emit a `backend://state` event, ignoring delivery failure (the frontend
+/// may not have a listener attached yet, which isn't fatal).
+fn emit_state(app_handle: &AppHandle, state: BackendState) {
+    let _ = app_handle.emit(BACKEND_STATE_EVENT, state);
+}
+
+/// Periodically check backend health and, after `FAILURE_THRESHOLD`
+/// consecutive failures, restart it via the same binary-resolution path
+/// used by `start`. Restarts are bounded by `policy` so a backend that
+/// can't stay up doesn't restart-loop forever; once the policy is
+/// exhausted the supervisor reports `Failed` and stops watching.
+///
+/// Returns a cancellation handle; sending on it stops the loop.
+///
+/// `port` is the shared cell `BackendProcess::port()` reads from; when
+/// `service.dynamic_port` is set, a restart updates it in place so callers
+/// never observe a port the child has since stopped listening on.
+fn spawn_supervisor(
+    process: Arc<AsyncMutex<Option<Child>>>,
+    service: ServiceConfig,
+    app_handle: AppHandle,
+    policy: RestartPolicy,
+    port: Arc<SyncMutex<u16>>,
+) -> oneshot::Sender<()> {
+    let (cancel_tx, mut cancel_rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let mut service = service;
+        let mut consecutive_failures = 0u32;
+        let mut restart_times: Vec<Instant> = Vec::new();
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel_rx => {
+                    log::info!("Backend supervisor stopping");
+                    return;
+                }
+                _ = tokio::time::sleep(HEALTH_CHECK_INTERVAL) => {}
+            }
+
+            let alive = {
+                let mut guard = process.lock().await;
+                match guard.as_mut() {
+                    Some(child) => matches!(child.try_wait(), Ok(None)),
+                    None => false,
+                }
+            };
+
+            let healthy = alive && check_health(service.port, &service.health_path).await;
 
-        while start.elapsed() < timeout {
-            if self.check_health().await {
-                return Ok(true);
+            if healthy {
+                if consecutive_failures > 0 {
+                    log::info!("Service '{}' health check recovered", service.name);
+                    emit_state(&app_handle, BackendState::Healthy);
+                }
+                consecutive_failures = 0;
+                continue;
+            }
+
+            consecutive_failures += 1;
+            log::warn!(
+                "Service '{}' health check failing ({}/{})",
+                service.name,
+                consecutive_failures,
+                FAILURE_THRESHOLD
+            );
+            emit_state(&app_handle, BackendState::Unhealthy);
+
+            if consecutive_failures < FAILURE_THRESHOLD {
+                continue;
+            }
+
+            let now = Instant::now();
+            restart_times.retain(|t| now.duration_since(*t) < policy.window);
+
+            if restart_times.len() as u32 >= policy.max_restarts {
+                log::error!(
+                    "Service '{}' exceeded {} restarts within {:?}, giving up",
+                    service.name,
+                    policy.max_restarts,
+                    policy.window
+                );
+                emit_state(&app_handle, BackendState::Failed);
+                return;
+            }
+
+            log::warn!(
+                "Restarting service '{}' after {} failed health checks",
+                service.name,
+                consecutive_failures
+            );
+            emit_state(&app_handle, BackendState::Restarting);
+            restart_times.push(now);
+            consecutive_failures = 0;
+
+            kill_locked_child(&process).await;
+            tokio::time::sleep(policy.backoff).await;
+
+            if service.dynamic_port {
+                match allocate_ephemeral_port() {
+                    Ok(new_port) => {
+                        log::info!(
+                            "Requesting ephemeral port {} for '{}' on restart",
+                            new_port, service.name
+                        );
+                        apply_port(&mut service, new_port);
+                        *port.lock().unwrap() = new_port;
+                    }
+                    Err(e) => {
+                        log::error!("Supervisor failed to allocate a port for service '{}': {}", service.name, e);
+                        emit_state(&app_handle, BackendState::Failed);
+                        return;
+                    }
+                }
+            }
+
+            let (new_child, log_file) = match spawn_child(&service, app_handle.clone()).await {
+                Ok(spawned) => spawned,
+                Err(e) => {
+                    log::error!("Supervisor failed to respawn service '{}': {}", service.name, e);
+                    emit_state(&app_handle, BackendState::Failed);
+                    return;
+                }
+            };
+
+            {
+                let mut guard = process.lock().await;
+                *guard = Some(new_child);
+            }
+
+            let timeout = Duration::from_secs(service.startup_timeout_secs);
+            match wait_for_ready(&process, service.port, &service.health_path, timeout, &log_file).await {
+                Ok(()) => {
+                    log::info!("Service '{}' restarted successfully", service.name);
+                    emit_state(&app_handle, BackendState::Healthy);
+                }
+                Err(e) => {
+                    log::error!("Service '{}' did not become ready after restart: {}", service.name, e);
+                    kill_locked_child(&process).await;
+                    emit_state(&app_handle, BackendState::Failed);
+                    return;
+                }
             }
-            tokio::time::sleep(check_interval).await;
         }
+    });
+
+    cancel_tx
+}
 
-        Ok(false)
+/// Spawn `service`'s command with stdout/stderr piped and forwarded,
+/// returning the running child and the path of its session log file (so
+/// callers can tail it into a diagnostic if the child doesn't come up).
+/// Shared by the initial `start` and by the supervisor's restart path so
+/// both launch a service identically.
+async fn spawn_child(service: &ServiceConfig, app_handle: AppHandle) -> Result<(Child, PathBuf), String> {
+    log::info!("Starting service '{}'", service.name);
+    log::info!("Command: {} {:?}", service.command, service.args);
+    log::info!("Port: {}", service.port);
+
+    // Create log files for this session
+    let log_file = create_backend_log_file(&service.name)?;
+    log::info!("Service log file: {:?}", log_file);
+
+    let mut cmd = Command::new(&service.command);
+    cmd.args(&service.args);
+
+    // Belt-and-suspenders: if the `Child` ever gets dropped without our own
+    // termination logic running first (e.g. a `stop()` that raced a
+    // supervisor restart and couldn't acquire the lock in time), make sure
+    // tokio kills the OS process instead of orphaning it.
+    cmd.kill_on_drop(true);
+
+    // Make the child its own process group leader so a later graceful
+    // shutdown can signal the whole group, not just this one process.
+    #[cfg(unix)]
+    {
+        cmd.process_group(0);
     }
 
-    /// Stop the backend process
-    pub fn stop(&mut self) {
-        if let Some(mut child) = self.process.take() {
-            println!("⏹️  Stopping backend...");
-            let _ = child.kill();
-            let _ = child.wait();
-        }
+    if let Some(working_dir) = &service.working_dir {
+        cmd.current_dir(working_dir);
     }
 
-    /// Check if the process is still running
-    pub fn is_running(&mut self) -> bool {
-        if let Some(ref mut child) = self.process {
-            match child.try_wait() {
-                Ok(None) => true,  // Still running
-                _ => false,         // Exited or error
+    for (key, value) in &service.env {
+        cmd.env(key, value);
+    }
+
+    // Pipe stdout/stderr instead of redirecting straight to a file, so
+    // they can be tailed to the log file AND forwarded to the frontend
+    // as they're produced.
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| {
+        log::error!("Failed to spawn service '{}': {}", service.name, e);
+        format!("Failed to start service '{}': {}", service.name, e)
+    })?;
+
+    log::info!("Service '{}' spawned with PID: {:?}", service.name, child.id());
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+    spawn_log_forwarder(stdout, "stdout", log_file.clone(), app_handle.clone());
+    spawn_log_forwarder(stderr, "stderr", log_file.clone(), app_handle);
+
+    Ok((child, log_file))
+}
+
+/// Check if a service is healthy by hitting its configured health endpoint.
+async fn check_health(port: u16, health_path: &str) -> bool {
+    let url = format!("http://127.0.0.1:{}{}", port, health_path);
+
+    match reqwest::get(&url).await {
+        Ok(response) => {
+            let is_ok = response.status().is_success();
+            if is_ok {
+                log::debug!("Health check passed");
+            } else {
+                log::warn!("Health check failed with status: {}", response.status());
             }
-        } else {
+            is_ok
+        }
+        Err(e) => {
+            log::warn!("Health check error: {}", e);
             false
         }
     }
 }
 
-impl Drop for BackendProcess {
-    fn drop(&mut self) {
-        self.stop();
+/// Wait for a service to be ready, polling both its health endpoint and
+/// whether the child is still alive. If the child exits before it becomes
+/// healthy, returns immediately (rather than waiting out the full timeout)
+/// with a diagnostic built from its exit status and the tail of `log_file`.
+async fn wait_for_ready(
+    process: &Arc<AsyncMutex<Option<Child>>>,
+    port: u16,
+    health_path: &str,
+    timeout: Duration,
+    log_file: &Path,
+) -> Result<(), String> {
+    let start = Instant::now();
+    let check_interval = Duration::from_millis(200);
+
+    while start.elapsed() < timeout {
+        let exit_status = {
+            let mut guard = process.lock().await;
+            match guard.as_mut() {
+                Some(child) => child.try_wait().map_err(|e| format!("Failed to poll child: {}", e))?,
+                None => None,
+            }
+        };
+
+        if let Some(status) = exit_status {
+            return Err(format_startup_failure(&describe_exit_status(status), log_file));
+        }
+
+        if check_health(port, health_path).await {
+            return Ok(());
+        }
+        tokio::time::sleep(check_interval).await;
+    }
+
+    Err(format!("did not become healthy within {:?}", timeout))
+}
+
+/// Describe a child's exit status for diagnostics: the terminating signal on
+/// Unix when it died by one, otherwise its exit code.
+fn describe_exit_status(status: std::process::ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("terminated by signal {}", signal);
+        }
+    }
+
+    match status.code() {
+        Some(code) => format!("exited with code {}", code),
+        None => "exited with an unknown status".to_string(),
+    }
+}
+
+/// Build a readiness-failure message combining `cause` with the last
+/// `LOG_TAIL_LINES` lines of `log_file`, so a crashed launch is diagnosable
+/// without hunting for the log file by hand.
+fn format_startup_failure(cause: &str, log_file: &Path) -> String {
+    format!(
+        "{} -- last {} lines of {:?}:\n{}",
+        cause,
+        LOG_TAIL_LINES,
+        log_file,
+        tail_log_file(log_file, LOG_TAIL_LINES)
+    )
+}
+
+/// Read the last `lines` lines of `path`, for embedding in diagnostics.
+fn tail_log_file(path: &Path, lines: usize) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            let mut tail: Vec<&str> = contents.lines().rev().take(lines).collect();
+            tail.reverse();
+            tail.join("\n")
+        }
+        Err(e) => format!("(failed to read log file: {})", e),
     }
 }
 
-/// Create a new backend log file with timestamp
-fn create_backend_log_file() -> Result<std::path::PathBuf, String> {
+/// Read `pipe` line-by-line until EOF, appending each line to the session
+/// log file and emitting it as a `backend://log` event for a live console.
+/// Stdout and stderr are forwarded by independent tasks so a slow reader on
+/// one stream never blocks draining the other.
+fn spawn_log_forwarder(
+    pipe: impl tokio::io::AsyncRead + Unpin + Send + 'static,
+    stream: &'static str,
+    log_file: std::path::PathBuf,
+    app_handle: AppHandle,
+) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(pipe).lines();
+
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("Failed to read backend {} output: {}", stream, e);
+                    break;
+                }
+            };
+
+            let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+            if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&log_file) {
+                use std::io::Write;
+                let _ = writeln!(file, "[{}] [{}] {}", timestamp, stream, line);
+            }
+
+            let _ = app_handle.emit(
+                BACKEND_LOG_EVENT,
+                BackendLogLine {
+                    stream,
+                    timestamp,
+                    text: line,
+                },
+            );
+        }
+    });
+}
+
+/// Create a new log file for `service_name`'s session, timestamped so
+/// restarts don't overwrite each other's output.
+fn create_backend_log_file(service_name: &str) -> Result<std::path::PathBuf, String> {
     use std::time::SystemTime;
-    
+
     // Import get_logs_dir from config module
     use crate::config::get_logs_dir;
-    
+
     let logs_dir = get_logs_dir();
-    
+
     // Create timestamp for log file
     let timestamp = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .map_err(|e| format!("Failed to get system time: {}", e))?
         .as_secs();
-    
-    let log_file = logs_dir.join(format!("backend-{}.log", timestamp));
-    
+
+    let log_file = logs_dir.join(format!("{}-{}.log", service_name, timestamp));
+
     // Create the file and write header
-    std::fs::write(&log_file, format!("=== Backend Log Started at {} ===\n", 
-        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")))
+    std::fs::write(&log_file, format!("=== {} log started at {} ===\n",
+        service_name, chrono::Local::now().format("%Y-%m-%d %H:%M:%S")))
         .map_err(|e| format!("Failed to create log file: {}", e))?;
-    
+
     Ok(log_file)
 }
 
+/// Resolve the backend interpreter/binary to launch, honoring config
+/// overrides before falling back to autodetection.
+///
+/// Precedence: `[app] backend_command` (a full command line) overrides
+/// `[app] python_path` (an interpreter to run `-m uvicorn` with) overrides
+/// autodetection via `which` and well-known per-OS install locations.
+fn resolve_backend_executable(config: &ConfigManager) -> Result<(String, Vec<String>), String> {
+    if let Some(command) = config.get("app", "backend_command") {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| "[app] backend_command is empty".to_string())?
+            .to_string();
+        let args = parts.map(str::to_string).collect();
+        log::info!("Using configured backend_command: {}", command);
+        return Ok((program, args));
+    }
+
+    if let Some(python_path) = config.get("app", "python_path") {
+        log::info!("Using configured python_path: {}", python_path);
+        return Ok((
+            python_path,
+            vec![
+                "-m".to_string(),
+                "uvicorn".to_string(),
+                "app.main:app".to_string(),
+            ],
+        ));
+    }
+
+    get_backend_binary_path()
+}
+
+/// Well-known per-OS locations to check for a binary when it isn't on PATH.
+fn well_known_install_locations(binary: &str) -> Vec<std::path::PathBuf> {
+    let mut locations = Vec::new();
+
+    if let Some(home) = dirs::home_dir() {
+        locations.push(home.join(".local").join("bin").join(binary));
+        locations.push(home.join(".pyenv").join("shims").join(binary));
+    }
+    locations.push(std::path::PathBuf::from("backend/.venv/bin").join(binary));
+    locations.push(std::path::PathBuf::from("/usr/local/bin").join(binary));
+    locations.push(std::path::PathBuf::from("/opt/homebrew/bin").join(binary));
+
+    locations
+}
+
+/// Search PATH (via the `which` crate) and well-known install locations for
+/// `binary`, returning the first hit or an error listing everywhere searched.
+fn find_executable(binary: &str) -> Result<String, String> {
+    if let Ok(path) = which::which(binary) {
+        log::info!("Found {} on PATH at {:?}", binary, path);
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    let mut searched = vec!["PATH".to_string()];
+    for candidate in well_known_install_locations(binary) {
+        searched.push(candidate.display().to_string());
+        if candidate.exists() {
+            log::info!("Found {} at {:?}", binary, candidate);
+            return Ok(candidate.to_string_lossy().to_string());
+        }
+    }
+
+    Err(format!(
+        "Could not find '{}'. Searched: {}. Set [app] python_path or backend_command in the config to override.",
+        binary,
+        searched.join(", ")
+    ))
+}
+
 /// Get the path to the backend binary
 /// Returns (program, args)
 fn get_backend_binary_path() -> Result<(String, Vec<String>), String> {
     #[cfg(debug_assertions)]
     {
-        eprintln!("🔧 [BACKEND] Using development mode (poetry + uvicorn)");
-        Ok(("poetry".to_string(), vec![
-            "run".to_string(),
-            "uvicorn".to_string(),
-            "app.main:app".to_string(),
-        ]))
-    }
-    
+        if which::which("poetry").is_ok() {
+            log::info!("Using development mode (poetry + uvicorn)");
+            return Ok((
+                "poetry".to_string(),
+                vec![
+                    "run".to_string(),
+                    "uvicorn".to_string(),
+                    "app.main:app".to_string(),
+                ],
+            ));
+        }
+
+        log::info!("poetry not found, falling back to a bare uvicorn");
+        let uvicorn = find_executable("uvicorn")?;
+        Ok((uvicorn, vec!["app.main:app".to_string()]))
+    }
+
     #[cfg(not(debug_assertions))]
     {
-        eprintln!("📦 [BACKEND] Using production mode (bundled binary)");
+        log::info!("Using production mode (bundled binary)");
         use std::env;
-        
+
         let exe_dir = env::current_exe()
             .map_err(|e| {
-                eprintln!("❌ [BACKEND] Failed to get executable path: {}", e);
+                log::error!("Failed to get executable path: {}", e);
                 format!("Failed to get executable path: {}", e)
             })?
             .parent()
             .ok_or_else(|| {
-                eprintln!("❌ [BACKEND] Failed to get parent directory");
+                log::error!("Failed to get parent directory");
                 "Failed to get parent directory".to_string()
             })?
             .to_path_buf();
-        
-        eprintln!("   Executable dir: {:?}", exe_dir);
-        
+
+        log::debug!("Executable dir: {:?}", exe_dir);
+
         let resources_dir = exe_dir
             .parent()
             .ok_or_else(|| {
-                eprintln!("❌ [BACKEND] Failed to get Contents directory");
+                log::error!("Failed to get Contents directory");
                 "Failed to get Contents directory".to_string()
             })?
             .join("Resources");
-        
-        eprintln!("   Resources dir: {:?}", resources_dir);
-        
+
+        log::debug!("Resources dir: {:?}", resources_dir);
+
         let binary_path = resources_dir
             .join("backend")
             .join("expense-manager-backend");
-        
-        eprintln!("   Looking for binary at: {:?}", binary_path);
-        
+
+        log::debug!("Looking for binary at: {:?}", binary_path);
+
         if !binary_path.exists() {
-            eprintln!("❌ [BACKEND] Binary not found!");
-            eprintln!("   Checked: {:?}", binary_path);
-            
+            log::error!("Backend binary not found");
+            log::error!("Checked: {:?}", binary_path);
+
             // List what's actually in the resources directory
             if let Ok(entries) = std::fs::read_dir(&resources_dir) {
-                eprintln!("   Contents of Resources directory:");
+                log::error!("Contents of Resources directory:");
                 for entry in entries.flatten() {
-                    eprintln!("     - {:?}", entry.path());
+                    log::error!("  - {:?}", entry.path());
                 }
             }
-            
+
             return Err(format!(
                 "Backend binary not found at {:?}",
                 binary_path
             ));
         }
-        
-        eprintln!("✓ [BACKEND] Binary found");
-        
+
+        log::info!("Backend binary found");
+
         // Make sure the binary is executable
         #[cfg(unix)]
         {
@@ -245,9 +1021,9 @@ fn get_backend_binary_path() -> Result<(String, Vec<String>), String> {
             permissions.set_mode(0o755);
             std::fs::set_permissions(&binary_path, permissions)
                 .map_err(|e| format!("Failed to set binary permissions: {}", e))?;
-            eprintln!("✓ [BACKEND] Binary permissions set to executable");
+            log::debug!("Binary permissions set to executable");
         }
-        
+
         Ok((
             binary_path
                 .to_str()
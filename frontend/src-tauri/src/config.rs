@@ -1,114 +1,270 @@
 use configparser::ini::Ini;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use dirs;
 
-/// Thread-safe configuration manager using INI format
+use crate::error::ConfigError;
+
+/// Environment variable prefix used for settings overrides, e.g.
+/// `EXPENSE_APP_PORT` overrides the `port` key of the `[app]` section.
+const ENV_PREFIX: &str = "EXPENSE";
+
+/// Fully-resolved, typed application settings.
+///
+/// Values are layered in precedence order: environment variables override
+/// the INI file, which overrides the compiled defaults below.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppSettings {
+    pub database_path: PathBuf,
+    pub port: u16,
+}
+
+/// Section/key pairs that must be resolvable before the settings database can
+/// even be opened (the database path is itself a setting), so they stay in
+/// the small bootstrap INI file instead of the `settings` table.
+const BOOTSTRAP_SECTION: &str = "app";
+const BOOTSTRAP_KEYS: &[&str] = &["database_path", "port"];
+
+fn is_bootstrap_key(section: &str, key: &str) -> bool {
+    section == BOOTSTRAP_SECTION && BOOTSTRAP_KEYS.contains(&key)
+}
+
+/// Best-effort coercion of a raw config string into the JSON type a
+/// numeric/bool field would need to deserialize from — `serde_json` treats
+/// a `Value::String` as just a string, it won't parse `"42"` into a number
+/// on T's behalf. Tries integer, then float, then bool, before giving up
+/// and keeping it a string.
+fn coerce_json_value(value: &str) -> serde_json::Value {
+    if let Ok(i) = value.parse::<i64>() {
+        return serde_json::Value::from(i);
+    }
+    if let Ok(f) = value.parse::<f64>() {
+        return serde_json::Value::from(f);
+    }
+    if let Ok(b) = value.parse::<bool>() {
+        return serde_json::Value::from(b);
+    }
+    serde_json::Value::String(value.to_string())
+}
+
+/// Thread-safe configuration manager.
+///
+/// Most settings live in a `settings(section, key, value)` table inside the
+/// same SQLite database the backend uses, so config changes participate in
+/// the DB's transactional guarantees and there's only one file to back up.
+/// `database_path` and `port` are the exception: resolving them is a
+/// prerequisite for opening that database, so they stay in a tiny bootstrap
+/// INI file read once on startup.
 #[derive(Clone)]
 pub struct ConfigManager {
-    ini: Arc<RwLock<Ini>>,
-    config_path: PathBuf,
+    db: Arc<Mutex<rusqlite::Connection>>,
+    bootstrap: Arc<RwLock<Ini>>,
+    bootstrap_path: PathBuf,
 }
 
 impl ConfigManager {
     /// Create a new ConfigManager instance
-    pub fn new() -> Result<Self, String> {
-        let config_path = get_config_path();
-        let mut ini = Ini::new();
+    pub fn new() -> Result<Self, ConfigError> {
+        Self::new_at(get_config_path())
+    }
 
-        // Load existing config or create default
-        if config_path.exists() {
-            ini.load(&config_path)
-                .map_err(|e| format!("Failed to load config: {}", e))?;
-        } else {
-            // Create default config
-            ini.set("app", "database_path", Some(get_default_db_path()));
-            
-            // Write default config to file
-            ini.write(&config_path)
-                .map_err(|e| format!("Failed to create default config: {}", e))?;
+    /// Like `new`, but with an injectable bootstrap file path. Tests use this
+    /// to point at a throwaway temp file (with `database_path` set to
+    /// `:memory:`) instead of the real user config and database.
+    fn new_at(bootstrap_path: PathBuf) -> Result<Self, ConfigError> {
+        let mut legacy_ini = Ini::new();
+        let mut legacy_values: HashMap<(String, String), String> = HashMap::new();
+
+        if bootstrap_path.exists() {
+            legacy_ini
+                .load(&bootstrap_path)
+                .map_err(ConfigError::IniParse)?;
+
+            // Anything beyond the bootstrap keys is a value from the old,
+            // full INI-backed config. Collect it so it can be migrated into
+            // the settings table below, then the bootstrap file is rewritten
+            // to hold only `database_path`/`port` going forward.
+            for section in legacy_ini.sections() {
+                if let Some(section_data) = legacy_ini.get_map_ref().get(&section) {
+                    for (key, value) in section_data {
+                        if is_bootstrap_key(&section, key) {
+                            continue;
+                        }
+                        if let Some(v) = value {
+                            legacy_values.insert((section.clone(), key.clone()), v.clone());
+                        }
+                    }
+                }
+            }
         }
 
+        let database_path = legacy_ini
+            .get(BOOTSTRAP_SECTION, "database_path")
+            .unwrap_or_else(get_default_db_path);
+
         // AUTO-GENERATION: Only if port is NOT defined in config, generate a fresh random port.
         // This ensures we respect any manual override in the config file.
-        // We do NOT call ini.save(), so this random port is ephemeral (memory only).
-        if ini.get("app", "port").is_none() {
-            #[cfg(debug_assertions)]
-            let port = 8000;
-
-            #[cfg(not(debug_assertions))]
-            let port = {
-                use std::collections::hash_map::RandomState;
-                use std::hash::{BuildHasher, Hash, Hasher};
-                
-                let random_state = RandomState::new();
-                let mut hasher = random_state.build_hasher();
-                std::time::SystemTime::now().hash(&mut hasher);
-                let random_offset = (hasher.finish() % 1000) as u16;
-                8000 + random_offset
-            };
-
-            ini.set("app", "port", Some(port.to_string()));
+        let port = match legacy_ini.get(BOOTSTRAP_SECTION, "port") {
+            Some(port) => port,
+            None => {
+                #[cfg(debug_assertions)]
+                let port = 8000;
+
+                #[cfg(not(debug_assertions))]
+                let port = {
+                    use std::collections::hash_map::RandomState;
+                    use std::hash::{BuildHasher, Hash, Hasher};
+
+                    let random_state = RandomState::new();
+                    let mut hasher = random_state.build_hasher();
+                    std::time::SystemTime::now().hash(&mut hasher);
+                    let random_offset = (hasher.finish() % 1000) as u16;
+                    8000 + random_offset
+                };
+
+                log::info!("No port configured, auto-generated port {}", port);
+                port.to_string()
+            }
+        };
+
+        let mut bootstrap = Ini::new();
+        bootstrap.set(BOOTSTRAP_SECTION, "database_path", Some(database_path.clone()));
+        bootstrap.set(BOOTSTRAP_SECTION, "port", Some(port));
+        bootstrap
+            .write(&bootstrap_path)
+            .map_err(ConfigError::IniParse)?;
+
+        let db = rusqlite::Connection::open(&database_path)?;
+        db.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                section TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (section, key)
+            )",
+            [],
+        )?;
+
+        let manager = Self {
+            db: Arc::new(Mutex::new(db)),
+            bootstrap: Arc::new(RwLock::new(bootstrap)),
+            bootstrap_path,
+        };
+
+        // One-time migration: move values from a pre-existing full INI file
+        // into the settings table now that it exists.
+        for ((section, key), value) in legacy_values {
+            manager.set(&section, &key, &value)?;
         }
 
-        Ok(Self {
-            ini: Arc::new(RwLock::new(ini)),
-            config_path,
-        })
+        Ok(manager)
     }
 
     /// Get a string value from config
     pub fn get(&self, section: &str, key: &str) -> Option<String> {
-        let ini = self.ini.read().unwrap();
-        ini.get(section, key)
+        if is_bootstrap_key(section, key) {
+            let bootstrap = self.bootstrap.read().unwrap();
+            return bootstrap.get(section, key);
+        }
+
+        let db = self.db.lock().unwrap();
+        db.query_row(
+            "SELECT value FROM settings WHERE section = ?1 AND key = ?2",
+            rusqlite::params![section, key],
+            |row| row.get(0),
+        )
+        .ok()
     }
 
     /// Set a string value in config
-    pub fn set(&self, section: &str, key: &str, value: &str) -> Result<(), String> {
-        let mut ini = self.ini.write().unwrap();
-        ini.set(section, key, Some(value.to_string()));
+    pub fn set(&self, section: &str, key: &str, value: &str) -> Result<(), ConfigError> {
+        if is_bootstrap_key(section, key) {
+            let mut bootstrap = self.bootstrap.write().unwrap();
+            bootstrap.set(section, key, Some(value.to_string()));
+            return Ok(());
+        }
+
+        let db = self.db.lock().unwrap();
+        db.execute(
+            "INSERT INTO settings (section, key, value) VALUES (?1, ?2, ?3)
+             ON CONFLICT(section, key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![section, key, value],
+        )?;
         Ok(())
     }
 
     /// Remove a key from config
-    pub fn remove(&self, section: &str, key: &str) -> Result<(), String> {
-        let mut ini = self.ini.write().unwrap();
-        ini.set(section, key, None);
+    pub fn remove(&self, section: &str, key: &str) -> Result<(), ConfigError> {
+        if is_bootstrap_key(section, key) {
+            let mut bootstrap = self.bootstrap.write().unwrap();
+            bootstrap.set(section, key, None);
+            return Ok(());
+        }
+
+        let db = self.db.lock().unwrap();
+        db.execute(
+            "DELETE FROM settings WHERE section = ?1 AND key = ?2",
+            rusqlite::params![section, key],
+        )?;
         Ok(())
     }
 
-    /// Save config to file
-    pub fn save(&self) -> Result<(), String> {
-        let ini = self.ini.read().unwrap();
-        ini.write(&self.config_path)
-            .map_err(|e| format!("Failed to save config: {}", e))
+    /// Save config to file (flushes the bootstrap INI; settings-table writes
+    /// are already durable as soon as `set`/`remove` return)
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let bootstrap = self.bootstrap.read().unwrap();
+        bootstrap
+            .write(&self.bootstrap_path)
+            .map_err(ConfigError::IniParse)
     }
 
     /// Reload config from file
-    pub fn reload(&self) -> Result<(), String> {
-        let mut ini = self.ini.write().unwrap();
-        ini.load(&self.config_path)
-            .map_err(|e| format!("Failed to reload config: {}", e))?;
+    pub fn reload(&self) -> Result<(), ConfigError> {
+        let mut bootstrap = self.bootstrap.write().unwrap();
+        bootstrap
+            .load(&self.bootstrap_path)
+            .map_err(ConfigError::IniParse)?;
         Ok(())
     }
 
     /// Get all sections and their key-value pairs
     pub fn get_all(&self) -> HashMap<String, HashMap<String, String>> {
-        let ini = self.ini.read().unwrap();
-        let mut result = HashMap::new();
-
-        for section in ini.sections() {
-            let mut section_map = HashMap::new();
-            if let Some(section_data) = ini.get_map_ref().get(&section) {
-                for (key, value) in section_data {
-                    if let Some(val) = value {
-                        section_map.insert(key.clone(), val.clone());
+        let mut result: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+        {
+            let bootstrap = self.bootstrap.read().unwrap();
+            for section in bootstrap.sections() {
+                if let Some(section_data) = bootstrap.get_map_ref().get(&section) {
+                    let entry = result.entry(section).or_default();
+                    for (key, value) in section_data {
+                        if let Some(val) = value {
+                            entry.insert(key.clone(), val.clone());
+                        }
                     }
                 }
             }
-            result.insert(section, section_map);
+        }
+
+        let db = self.db.lock().unwrap();
+        let mut stmt = db
+            .prepare("SELECT section, key, value FROM settings")
+            .expect("Failed to prepare settings query");
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .expect("Failed to query settings");
+
+        for (section, key, value) in rows.flatten() {
+            result.entry(section).or_default().insert(key, value);
         }
 
         result
@@ -124,7 +280,7 @@ impl ConfigManager {
     }
 
     /// Set a boolean value
-    pub fn set_bool(&self, section: &str, key: &str, value: bool) -> Result<(), String> {
+    pub fn set_bool(&self, section: &str, key: &str, value: bool) -> Result<(), ConfigError> {
         self.set(section, key, &value.to_string())
     }
 
@@ -136,7 +292,7 @@ impl ConfigManager {
     }
 
     /// Set an integer value
-    pub fn set_int(&self, section: &str, key: &str, value: i64) -> Result<(), String> {
+    pub fn set_int(&self, section: &str, key: &str, value: i64) -> Result<(), ConfigError> {
         self.set(section, key, &value.to_string())
     }
 
@@ -148,25 +304,35 @@ impl ConfigManager {
     }
 
     /// Set a float value
-    pub fn set_float(&self, section: &str, key: &str, value: f64) -> Result<(), String> {
+    pub fn set_float(&self, section: &str, key: &str, value: f64) -> Result<(), ConfigError> {
         self.set(section, key, &value.to_string())
     }
 
     // Convenience methods for common config values
 
-    /// Get the database path
+    /// Get the database path, honoring `EXPENSE_APP_DATABASE_PATH` before
+    /// falling back to the configured (or default) value.
     pub fn get_database_path(&self) -> String {
-        self.get("app", "database_path")
+        self.env_override("app", "database_path")
+            .or_else(|| self.get("app", "database_path"))
             .unwrap_or_else(get_default_db_path)
     }
 
     /// Set the database path
-    pub fn set_database_path(&self, path: &str) -> Result<(), String> {
+    pub fn set_database_path(&self, path: &str) -> Result<(), ConfigError> {
         self.set("app", "database_path", path)
     }
 
-    /// Get the backend port (auto-generates random port if not set)
-    pub fn get_port(&self) -> Result<u16, String> {
+    /// Get the backend port, honoring `EXPENSE_APP_PORT` before falling back
+    /// to the configured value (auto-generated if not set).
+    pub fn get_port(&self) -> Result<u16, ConfigError> {
+        if let Some(port) = self
+            .env_override("app", "port")
+            .and_then(|v| v.parse::<u16>().ok())
+        {
+            return Ok(port);
+        }
+
         // Port is guaranteed to be in the config (injected in new)
         // We use get_int which handles the parsing
         // Default to 8000 just in case, though the key should exist.
@@ -174,10 +340,121 @@ impl ConfigManager {
     }
 
     /// Set the backend port
-    pub fn set_port(&self, port: u16) -> Result<(), String> {
+    pub fn set_port(&self, port: u16) -> Result<(), ConfigError> {
         self.set_int("app", "port", port as i64)?;
         self.save()
     }
+
+    // Typed configuration
+
+    /// Deserialize an entire INI section into a typed struct.
+    ///
+    /// Every value in the section is stored as a raw string; `serde_json`
+    /// won't coerce a JSON string into a numeric/bool field, so each value
+    /// is first best-effort parsed into the JSON type it looks like (via
+    /// `coerce_json_value`) before `T`'s `Deserialize` impl runs.
+    pub fn get_typed<T: DeserializeOwned>(&self, section: &str) -> Result<T, ConfigError> {
+        let mut map = serde_json::Map::new();
+
+        if let Some(section_data) = self.get_all().remove(section) {
+            for (key, value) in section_data {
+                map.insert(key, coerce_json_value(&value));
+            }
+        }
+
+        serde_json::from_value(serde_json::Value::Object(map)).map_err(|e| {
+            ConfigError::InvalidValue {
+                section: section.to_string(),
+                key: "<section>".to_string(),
+                expected: e.to_string(),
+            }
+        })
+    }
+
+    /// Resolve the fully-layered `AppSettings`, applying environment variable
+    /// overrides on top of the INI file and compiled defaults.
+    ///
+    /// Built on `get_database_path`/`get_port`, which already apply the env
+    /// overrides; this just additionally resolves the database path relative
+    /// to the config directory.
+    pub fn resolve(&self) -> Result<AppSettings, ConfigError> {
+        Ok(AppSettings {
+            database_path: self.resolve_path(&self.get_database_path()),
+            port: self.get_port()?,
+        })
+    }
+
+    /// Look up `{ENV_PREFIX}_{SECTION}_{KEY}`, uppercased with dashes turned
+    /// into underscores, e.g. `("app", "database_path")` -> `EXPENSE_APP_DATABASE_PATH`.
+    fn env_override(&self, section: &str, key: &str) -> Option<String> {
+        let var_name = format!(
+            "{}_{}_{}",
+            ENV_PREFIX,
+            section.to_uppercase(),
+            key.to_uppercase().replace('-', "_")
+        );
+        std::env::var(var_name).ok()
+    }
+
+    /// Interpret a path-valued setting relative to the config directory when
+    /// it isn't already absolute.
+    fn resolve_path(&self, value: &str) -> PathBuf {
+        let path = PathBuf::from(value);
+        if path.is_absolute() {
+            path
+        } else {
+            self.bootstrap_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(path)
+        }
+    }
+}
+
+/// Display name the OS shows for the start-on-login registration.
+const AUTO_LAUNCH_APP_NAME: &str = "Expense Manager";
+
+fn build_auto_launcher() -> Result<auto_launch::AutoLaunch, ConfigError> {
+    let exe_path = std::env::current_exe()?;
+    let exe_path = exe_path.to_str().ok_or_else(|| ConfigError::AutoLaunch(
+        "executable path is not valid UTF-8".to_string(),
+    ))?;
+
+    Ok(auto_launch::AutoLaunch::new(
+        AUTO_LAUNCH_APP_NAME,
+        exe_path,
+        &[] as &[&str],
+    ))
+}
+
+/// Query whether the app is currently registered to launch at login.
+pub fn is_auto_launch_enabled() -> Result<bool, ConfigError> {
+    build_auto_launcher()?
+        .is_enabled()
+        .map_err(|e| ConfigError::AutoLaunch(e.to_string()))
+}
+
+/// Enable or disable launching the app at login.
+///
+/// Idempotent: the OS's current auto-launch state is queried first, and
+/// `enable`/`disable` is only invoked when it differs from `enabled`, so
+/// reconciling on every startup doesn't create duplicate registry/plist
+/// entries.
+pub fn set_auto_launch(enabled: bool) -> Result<(), ConfigError> {
+    let launcher = build_auto_launcher()?;
+    let currently_enabled = launcher.is_enabled().unwrap_or(false);
+
+    if currently_enabled == enabled {
+        return Ok(());
+    }
+
+    let result = if enabled {
+        launcher.enable()
+    } else {
+        launcher.disable()
+    };
+
+    result.map_err(|e| ConfigError::AutoLaunch(e.to_string()))
 }
 
 /// Get the platform-specific configuration file path
@@ -246,6 +523,27 @@ pub fn get_default_db_path() -> String {
         .to_string()
 }
 
+/// Get the platform-specific path to the declarative services file
+/// (`services.yaml` by default; a `services.toml` in the same directory is
+/// used instead when there is no `services.yaml`).
+///
+/// This lives alongside the bootstrap config rather than in the settings
+/// table since, like `database_path`/`port`, it has to be readable before
+/// anything can be started.
+pub fn get_services_config_path() -> PathBuf {
+    let dir = get_config_path()
+        .parent()
+        .expect("config path has no parent directory")
+        .to_path_buf();
+    let yaml_path = dir.join("services.yaml");
+    let toml_path = dir.join("services.toml");
+    if !yaml_path.exists() && toml_path.exists() {
+        toml_path
+    } else {
+        yaml_path
+    }
+}
+
 /// Get the platform-specific logs directory
 pub fn get_logs_dir() -> PathBuf {
     let logs_dir = if cfg!(target_os = "macos") {
@@ -278,6 +576,36 @@ pub fn get_logs_dir() -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Build a `ConfigManager` backed by a throwaway temp bootstrap file and
+    /// an in-memory database, so tests never touch the real user config or
+    /// `expense.db`, and each test gets its own isolated database (`:memory:`
+    /// connections aren't shared across `ConfigManager` instances).
+    fn test_manager() -> ConfigManager {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "expense-manager-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&dir).expect("failed to create temp config dir");
+
+        let bootstrap_path = dir.join("config");
+        let mut bootstrap = Ini::new();
+        bootstrap.set(BOOTSTRAP_SECTION, "database_path", Some(":memory:".to_string()));
+        bootstrap.set(BOOTSTRAP_SECTION, "port", Some("8000".to_string()));
+        bootstrap
+            .write(&bootstrap_path)
+            .expect("failed to write test bootstrap file");
+
+        ConfigManager::new_at(bootstrap_path).expect("failed to create test config manager")
+    }
+
+    /// Serializes tests that mutate process-global env vars, so they don't
+    /// race against each other when `cargo test` runs them concurrently.
+    static ENV_VAR_LOCK: Mutex<()> = Mutex::new(());
 
     #[test]
     fn test_config_paths() {
@@ -286,11 +614,14 @@ mod tests {
 
         let db_path = get_default_db_path();
         assert!(db_path.contains("expense.db"));
+
+        let services_path = get_services_config_path();
+        assert_eq!(services_path.file_name().unwrap(), "services.yaml");
     }
 
     #[test]
     fn test_config_manager() {
-        let manager = ConfigManager::new().expect("Failed to create config manager");
+        let manager = test_manager();
 
         // Test string operations
         manager.set("test", "key1", "value1").unwrap();
@@ -313,9 +644,24 @@ mod tests {
         assert!(!db_path.is_empty());
     }
 
+    #[test]
+    fn test_resolve_env_override() {
+        let _guard = ENV_VAR_LOCK.lock().unwrap();
+        let manager = test_manager();
+        manager.set("app", "port", "9000").unwrap();
+
+        std::env::set_var("EXPENSE_APP_PORT", "9100");
+        let settings = manager.resolve().expect("Failed to resolve settings");
+        assert_eq!(settings.port, 9100);
+        std::env::remove_var("EXPENSE_APP_PORT");
+
+        let settings = manager.resolve().expect("Failed to resolve settings");
+        assert_eq!(settings.port, 9000);
+    }
+
     #[test]
     fn test_get_all() {
-        let manager = ConfigManager::new().expect("Failed to create config manager");
+        let manager = test_manager();
         manager.set("section1", "key1", "value1").unwrap();
         manager.set("section2", "key2", "value2").unwrap();
 
@@ -323,4 +669,27 @@ mod tests {
         assert!(all.contains_key("section1"));
         assert!(all.contains_key("section2"));
     }
+
+    #[test]
+    fn test_get_typed_coerces_numeric_and_bool_fields() {
+        #[derive(Deserialize)]
+        struct Section {
+            count: u32,
+            ratio: f64,
+            enabled: bool,
+            name: String,
+        }
+
+        let manager = test_manager();
+        manager.set("typed", "count", "42").unwrap();
+        manager.set("typed", "ratio", "3.5").unwrap();
+        manager.set("typed", "enabled", "true").unwrap();
+        manager.set("typed", "name", "widget").unwrap();
+
+        let section: Section = manager.get_typed("typed").expect("Failed to deserialize section");
+        assert_eq!(section.count, 42);
+        assert!((section.ratio - 3.5).abs() < 0.001);
+        assert!(section.enabled);
+        assert_eq!(section.name, "widget");
+    }
 }
@@ -0,0 +1,65 @@
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+/// Errors surfaced by [`crate::config::ConfigManager`].
+///
+/// Kept distinct from [`AppError`] so config-only call sites (e.g. tests)
+/// can match on it without going through the app-wide error type.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("failed to parse config file: {0}")]
+    IniParse(String),
+
+    #[error("missing required config value [{section}] {key}")]
+    MissingKey { section: String, key: String },
+
+    #[error("invalid value for [{section}] {key}: expected {expected}")]
+    InvalidValue {
+        section: String,
+        key: String,
+        expected: String,
+    },
+
+    #[error("failed to configure start-on-login: {0}")]
+    AutoLaunch(String),
+
+    #[error("failed to initialize logging: {0}")]
+    Logging(String),
+}
+
+/// App-wide error type returned by Tauri commands.
+///
+/// Implements `Serialize` so the frontend receives a structured error
+/// instead of an opaque string.
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[error("failed to start backend: {0}")]
+    BackendStart(String),
+}
+
+impl Serialize for ConfigError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
@@ -2,15 +2,18 @@
 
 mod config;
 mod backend_process;
+mod error;
+mod logging;
 
 use config::{ConfigManager, get_config_path, get_default_db_path};
+use error::AppError;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::{Manager, State};
 
 // Global backend process state
 struct AppState {
-    backend: Mutex<Option<backend_process::BackendProcess>>,
+    backend: Mutex<Option<backend_process::BackendManager>>,
     config: Arc<Mutex<ConfigManager>>,
 }
 
@@ -26,7 +29,7 @@ fn get_config_value(
     state: State<'_, AppState>,
     section: String,
     key: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, AppError> {
     let config = state.config.lock().unwrap();
     Ok(config.get(&section, &key))
 }
@@ -37,7 +40,7 @@ fn set_config_value(
     section: String,
     key: String,
     value: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let config = state.config.lock().unwrap();
     config.set(&section, &key, &value)?;
     config.save()?;
@@ -49,7 +52,7 @@ fn remove_config_value(
     state: State<'_, AppState>,
     section: String,
     key: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let config = state.config.lock().unwrap();
     config.remove(&section, &key)?;
     config.save()?;
@@ -59,7 +62,7 @@ fn remove_config_value(
 #[tauri::command]
 fn get_all_config(
     state: State<'_, AppState>,
-) -> Result<HashMap<String, HashMap<String, String>>, String> {
+) -> Result<HashMap<String, HashMap<String, String>>, AppError> {
     let config = state.config.lock().unwrap();
     Ok(config.get_all())
 }
@@ -67,15 +70,13 @@ fn get_all_config(
 // Legacy compatibility commands
 
 #[tauri::command]
-fn get_config_file_path() -> Result<String, String> {
+fn get_config_file_path() -> Result<String, AppError> {
     let path = get_config_path();
-    path.to_str()
-        .ok_or("Failed to convert path to string".to_string())
-        .map(|s| s.to_string())
+    Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-fn get_database_path(state: State<'_, AppState>) -> Result<String, String> {
+fn get_database_path(state: State<'_, AppState>) -> Result<String, AppError> {
     #[cfg(debug_assertions)]
     return Ok("(The client does not control the webserver in debug mode)".to_string());
 
@@ -89,8 +90,9 @@ fn get_database_path(state: State<'_, AppState>) -> Result<String, String> {
 #[tauri::command]
 async fn set_database_path(
     path: String,
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     // Update config file
     {
         let config = state.config.lock().unwrap();
@@ -99,7 +101,7 @@ async fn set_database_path(
     }
 
     // Restart backend with new database path
-    restart_backend(&state, &path).await?;
+    restart_backend(&state, app, &path).await?;
 
     Ok(())
 }
@@ -110,34 +112,106 @@ fn get_default_database_path() -> String {
 }
 
 #[tauri::command]
-async fn pick_database_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn pick_database_file(app: tauri::AppHandle) -> Result<Option<String>, AppError> {
     use tauri_plugin_dialog::DialogExt;
-    
+
     let file_path = app.dialog()
         .file()
         .set_title("Select Database Location")
         .add_filter("SQLite Database", &["db", "sqlite", "sqlite3"])
         .blocking_save_file();
-    
+
     Ok(file_path.and_then(|p| p.as_path().map(|path| path.to_string_lossy().to_string())))
 }
 
+/// Get the port the backend is actually listening on.
+///
+/// Prefers the running `BackendManager`'s resolved port (which may differ
+/// from the configured one when `dynamic_port` let the OS choose it, and
+/// changes across restarts), falling back to the configured port when the
+/// backend hasn't been started yet (e.g. dev mode).
+#[tauri::command]
+fn get_backend_port(state: State<'_, AppState>) -> Result<u16, AppError> {
+    if let Some(port) = state.backend.lock().unwrap().as_ref().and_then(|b| b.port("backend")) {
+        return Ok(port);
+    }
+
+    let config = state.config.lock().unwrap();
+    Ok(config.get_port()?)
+}
+
+// Start-on-login commands
+
+#[tauri::command]
+fn get_auto_launch() -> Result<bool, AppError> {
+    Ok(config::is_auto_launch_enabled()?)
+}
+
 #[tauri::command]
-fn get_backend_port(state: State<'_, AppState>) -> Result<u16, String> {
+fn set_auto_launch(enabled: bool, state: State<'_, AppState>) -> Result<(), AppError> {
+    config::set_auto_launch(enabled)?;
+
     let config = state.config.lock().unwrap();
-    config.get_port()
+    config.set_bool("app", "start_on_login", enabled)?;
+    config.save()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+fn get_logs_dir() -> String {
+    config::get_logs_dir().to_string_lossy().to_string()
+}
+
+#[tauri::command]
+async fn pick_backend_executable(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let file_path = app
+        .dialog()
+        .file()
+        .set_title("Select Python Interpreter")
+        .blocking_pick_file();
+
+    let Some(path) = file_path.and_then(|p| p.as_path().map(|path| path.to_string_lossy().to_string())) else {
+        return Ok(None);
+    };
+
+    {
+        let config = state.config.lock().unwrap();
+        config.set("app", "python_path", &path)?;
+        config.save()?;
+    }
+
+    let database_path = {
+        let config = state.config.lock().unwrap();
+        config.get_database_path()
+    };
+    restart_backend(&state, app, &database_path).await?;
+
+    Ok(Some(path))
 }
 
 /// Start the backend process with the configured database path
-async fn start_backend(state: &tauri::State<'_, AppState>) -> Result<(), String> {
-    let (database_path, port) = {
+async fn start_backend(
+    state: &tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), AppError> {
+    let (database_path, port, config) = {
         let config = state.config.lock().unwrap();
         let db = config.get_database_path();
         let port = config.get_port()?;
-        (db, port)
+        (db, port, config.clone())
     };
 
-    let backend = backend_process::BackendProcess::start(&database_path, port).await?;
+    let backend = backend_process::BackendManager::start(&database_path, port, &config, app_handle)
+        .await
+        .map_err(AppError::BackendStart)?;
+
+    log::info!("Backend services started");
 
     let mut guard = state.backend.lock().unwrap();
     *guard = Some(backend);
@@ -146,21 +220,32 @@ async fn start_backend(state: &tauri::State<'_, AppState>) -> Result<(), String>
 }
 
 /// Restart the backend with a new database path
-async fn restart_backend(state: &tauri::State<'_, AppState>, database_path: &str) -> Result<(), String> {
+async fn restart_backend(
+    state: &tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    database_path: &str,
+) -> Result<(), AppError> {
     // Stop existing backend
     {
         let mut guard = state.backend.lock().unwrap();
         *guard = None; // Drop will stop the process
     }
 
-    // Get port from config
-    let port = {
+    // Get port and config from config manager
+    let (port, config) = {
         let config = state.config.lock().unwrap();
-        config.get_port()?
+        (config.get_port()?, config.clone())
     };
 
     // Start new backend
-    let backend = backend_process::BackendProcess::start(database_path, port).await?;
+    let backend = backend_process::BackendManager::start(database_path, port, &config, app_handle)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to restart backend: {}", e);
+            AppError::BackendStart(e)
+        })?;
+
+    log::info!("Backend services restarted");
 
     let mut guard = state.backend.lock().unwrap();
     *guard = Some(backend);
@@ -174,6 +259,19 @@ pub fn run() {
     let config_manager = ConfigManager::new()
         .expect("Failed to initialize configuration");
 
+    if let Err(e) = logging::init(&config_manager) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    // Reconcile the OS's start-on-login registration with the stored
+    // preference; covers drift (user removed it via OS settings). Only the
+    // enabled/disabled state is compared, so an existing registration is
+    // left as it is, even if the app binary has moved since.
+    let start_on_login = config_manager.get_bool("app", "start_on_login", false);
+    if let Err(e) = config::set_auto_launch(start_on_login) {
+        log::error!("Failed to reconcile start-on-login setting: {}", e);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -192,8 +290,8 @@ pub fn run() {
                 std::thread::spawn(move || {
                     tauri::async_runtime::block_on(async move {
                         let state = app_handle.state::<AppState>();
-                        if let Err(e) = start_backend(&state).await {
-                            eprintln!("Failed to start backend: {}", e);
+                        if let Err(e) = start_backend(&state, app_handle.clone()).await {
+                            log::error!("Failed to start backend: {}", e);
                             use tauri_plugin_dialog::DialogExt;
                             use tauri_plugin_dialog::MessageDialogKind;
                             
@@ -209,9 +307,9 @@ pub fn run() {
             
             #[cfg(debug_assertions)]
             {
-                eprintln!("🔧 [DEV MODE] Skipping backend auto-start");
-                eprintln!("   Make sure backend is running at http://localhost:8000");
-                eprintln!("   Run: cd backend && poetry run uvicorn app.main:app --reload --port 8000");
+                log::info!("[DEV MODE] Skipping backend auto-start");
+                log::info!("Make sure backend is running at http://localhost:8000");
+                log::info!("Run: cd backend && poetry run uvicorn app.main:app --reload --port 8000");
             }
             
             Ok(())
@@ -228,6 +326,10 @@ pub fn run() {
             get_default_database_path,
             pick_database_file,
             get_backend_port,
+            get_auto_launch,
+            set_auto_launch,
+            get_logs_dir,
+            pick_backend_executable,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
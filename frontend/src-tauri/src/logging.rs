@@ -0,0 +1,36 @@
+use crate::config::{get_logs_dir, ConfigManager};
+use crate::error::ConfigError;
+
+/// Initialize the `log` facade with a file backend that writes into
+/// `get_logs_dir()`, in a file named after the startup date
+/// (`app-YYYY-MM-DD.log`). The file is chosen once here; a run that spans
+/// midnight keeps writing to the file of the day it started.
+///
+/// The level is driven by `[app] log_level` (any value `log::LevelFilter`
+/// parses, e.g. `debug`/`info`/`warn`), defaulting to `info`.
+pub fn init(config: &ConfigManager) -> Result<(), ConfigError> {
+    let level = config
+        .get("app", "log_level")
+        .and_then(|v| v.parse::<log::LevelFilter>().ok())
+        .unwrap_or(log::LevelFilter::Info);
+
+    let log_file_name = format!("app-{}.log", chrono::Local::now().format("%Y-%m-%d"));
+    let log_path = get_logs_dir().join(log_file_name);
+
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "[{} {} {}] {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                record.level(),
+                record.target(),
+                message
+            ))
+        })
+        .level(level)
+        .chain(fern::log_file(&log_path).map_err(|e| ConfigError::Logging(e.to_string()))?)
+        .apply()
+        .map_err(|e| ConfigError::Logging(e.to_string()))?;
+
+    Ok(())
+}